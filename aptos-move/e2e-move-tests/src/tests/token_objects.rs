@@ -3,7 +3,10 @@
 
 use crate::{assert_success, tests::common, MoveHarness};
 use aptos_types::account_address::{self, AccountAddress};
-use move_core_types::{identifier::Identifier, language_storage::StructTag};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize, Eq, PartialEq)]
@@ -151,3 +154,117 @@ fn test_basic_token() {
 
     assert!(h.read_resource_group(&token_id, obj_group_tag).is_none());
 }
+
+#[test]
+fn test_object_introspection_natives() {
+    let mut h = MoveHarness::new();
+
+    let addr = AccountAddress::from_hex_literal("0xcafe").unwrap();
+    let account = h.new_account_at(addr);
+
+    let mut build_options = aptos_framework::BuildOptions::default();
+    build_options
+        .named_addresses
+        .insert("token_objects".to_string(), addr);
+
+    let result = h.publish_package_with_options(
+        &account,
+        &common::test_dir_path("../../../move-examples/token_objects"),
+        build_options,
+    );
+    assert_success!(result);
+
+    let result = h.run_entry_function(
+        &account,
+        str::parse("0x1::object::init_store").unwrap(),
+        vec![],
+        vec![],
+    );
+    assert_success!(result);
+
+    let result = h.run_entry_function(
+        &account,
+        str::parse(&format!("0x{}::example::mint_gem", addr)).unwrap(),
+        vec![],
+        vec![
+            bcs::to_bytes::<u64>(&32).unwrap(),
+            bcs::to_bytes::<u64>(&32).unwrap(),
+            bcs::to_bytes("Beautiful specimen!").unwrap(),
+            bcs::to_bytes("earth").unwrap(),
+            bcs::to_bytes("jade").unwrap(),
+            bcs::to_bytes("404").unwrap(),
+        ],
+    );
+    assert_success!(result);
+
+    let token_id = account_address::create_token_id(addr, "Hero Quest!", "jade");
+    let missing_addr = AccountAddress::from_hex_literal("0xdead").unwrap();
+    let token_obj_tag = StructTag {
+        address: addr,
+        module: Identifier::new("token").unwrap(),
+        name: Identifier::new("Token").unwrap(),
+        type_params: vec![],
+    };
+    let obj_group_tag = StructTag {
+        address: AccountAddress::from_hex_literal("0x1").unwrap(),
+        module: Identifier::new("object").unwrap(),
+        name: Identifier::new("ObjectGroup").unwrap(),
+        type_params: vec![],
+    };
+
+    // exists_batch should report the minted token as present and an unrelated address as absent.
+    let result = h.execute_view_function(
+        str::parse("0x1::object::exists_batch").unwrap(),
+        vec![TypeTag::Struct(Box::new(token_obj_tag.clone()))],
+        vec![bcs::to_bytes(&vec![token_id, missing_addr]).unwrap()],
+    );
+    let exists: Vec<bool> = bcs::from_bytes(&result.values.unwrap()[0]).unwrap();
+    assert_eq!(exists, vec![true, false]);
+
+    // The group backing the token holds both the `Object` and `Token` members.
+    let result = h.execute_view_function(
+        str::parse("0x1::object::resource_group_size").unwrap(),
+        vec![TypeTag::Struct(Box::new(obj_group_tag.clone()))],
+        vec![bcs::to_bytes(&token_id).unwrap()],
+    );
+    let size: u64 = bcs::from_bytes(&result.values.unwrap()[0]).unwrap();
+    assert_eq!(size, 2);
+
+    let result = h.execute_view_function(
+        str::parse("0x1::object::resource_group_types").unwrap(),
+        vec![TypeTag::Struct(Box::new(obj_group_tag))],
+        vec![bcs::to_bytes(&token_id).unwrap()],
+    );
+    let types: Vec<String> = bcs::from_bytes(&result.values.unwrap()[0]).unwrap();
+    assert_eq!(types.len(), 2);
+    assert!(types.iter().any(|tag| tag.ends_with("::object::Object")));
+    assert!(types.iter().any(|tag| tag.ends_with("::token::Token")));
+
+    // resource_version must advance once the token resource is mutated.
+    let result = h.execute_view_function(
+        str::parse("0x1::object::resource_version").unwrap(),
+        vec![TypeTag::Struct(Box::new(token_obj_tag.clone()))],
+        vec![bcs::to_bytes(&token_id).unwrap()],
+    );
+    let version_before: u64 = bcs::from_bytes(&result.values.unwrap()[0]).unwrap();
+
+    let result = h.run_entry_function(
+        &account,
+        str::parse(&format!("0x{}::token::update_description", addr)).unwrap(),
+        vec![],
+        vec![
+            bcs::to_bytes("Hero Quest!").unwrap(),
+            bcs::to_bytes("jade").unwrap(),
+            bcs::to_bytes("Heck no!").unwrap(),
+        ],
+    );
+    assert_success!(result);
+
+    let result = h.execute_view_function(
+        str::parse("0x1::object::resource_version").unwrap(),
+        vec![TypeTag::Struct(Box::new(token_obj_tag))],
+        vec![bcs::to_bytes(&token_id).unwrap()],
+    );
+    let version_after: u64 = bcs::from_bytes(&result.values.unwrap()[0]).unwrap();
+    assert!(version_after > version_before);
+}
@@ -1,27 +1,116 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use better_any::{Tid, TidAble};
 use move_binary_format::errors::{PartialVMError, PartialVMResult};
 use move_core_types::{
-    account_address::AccountAddress, gas_algebra::InternalGas,
-    vm_status::StatusCode, language_storage::TypeTag,
+    account_address::AccountAddress,
+    gas_algebra::{InternalGas, InternalGasPerArg, InternalGasPerByte, NumArgs, NumBytes},
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+    vm_status::StatusCode,
 };
 use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
 use move_vm_types::{
-    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
+    loaded_data::runtime_types::Type,
+    natives::function::NativeResult,
+    pop_arg,
+    values::{Struct, Value, Vector},
 };
 use smallvec::smallvec;
 use std::{collections::VecDeque, sync::Arc};
 
+/***************************************************************************************************
+ * extension: NativeObjectContext
+ *
+ *   The natives below need storage-level facts (loaded byte sizes, resource-group membership,
+ *   per-key write versions) that a `Value` returned by `get_resource` no longer carries once it
+ *   has been deserialized. Rather than growing `NativeContext` itself, the embedder registers an
+ *   `ObjectResolver` as a `NativeContext` extension (the same mechanism `NativeTableContext` and
+ *   friends use) before running a session that loads this module's natives.
+ *
+ **************************************************************************************************/
+pub trait ObjectResolver {
+    /// Size in bytes of `(address, tag)` as last loaded from storage, or `None` if it was never
+    /// materialized (e.g. the resource does not exist).
+    fn resource_size(
+        &self,
+        address: AccountAddress,
+        tag: &StructTag,
+    ) -> PartialVMResult<Option<NumBytes>>;
+
+    /// Struct tags of the members physically packed into the resource group `group_tag` at
+    /// `address`.
+    fn resource_group_members(
+        &self,
+        address: AccountAddress,
+        group_tag: &StructTag,
+    ) -> PartialVMResult<Vec<StructTag>>;
+
+    /// Monotonically increasing counter the state store advances every time `(address, tag)` is
+    /// written.
+    fn resource_version(&self, address: AccountAddress, tag: &StructTag) -> PartialVMResult<u64>;
+}
+
+#[derive(Tid)]
+pub struct NativeObjectContext<'a> {
+    resolver: &'a dyn ObjectResolver,
+}
+
+impl<'a> NativeObjectContext<'a> {
+    pub fn new(resolver: &'a dyn ObjectResolver) -> Self {
+        Self { resolver }
+    }
+}
+
+fn resource_size(
+    context: &NativeContext,
+    address: AccountAddress,
+    tag: &StructTag,
+) -> PartialVMResult<Option<NumBytes>> {
+    context
+        .extensions()
+        .get::<NativeObjectContext>()
+        .resolver
+        .resource_size(address, tag)
+}
+
+fn resource_group_members(
+    context: &NativeContext,
+    address: AccountAddress,
+    group_tag: &StructTag,
+) -> PartialVMResult<Vec<StructTag>> {
+    context
+        .extensions()
+        .get::<NativeObjectContext>()
+        .resolver
+        .resource_group_members(address, group_tag)
+}
+
+fn resource_version(
+    context: &NativeContext,
+    address: AccountAddress,
+    tag: &StructTag,
+) -> PartialVMResult<u64> {
+    context
+        .extensions()
+        .get::<NativeObjectContext>()
+        .resolver
+        .resource_version(address, tag)
+}
+
 /***************************************************************************************************
  * native exists_at<T>
  *
- *   gas cost: base_cost
+ *   gas cost: base_cost + per_byte_cost * size_of_resource (when the resource group backing
+ *             the resource is actually loaded from storage)
  *
  **************************************************************************************************/
 #[derive(Clone, Debug)]
 pub struct ExistsAtGasParameters {
     pub base_cost: InternalGas,
+    pub per_item_cost: InternalGasPerArg,
+    pub per_byte_cost: InternalGasPerByte,
 }
 
 fn native_exists_at(
@@ -45,29 +134,249 @@ fn native_exists_at(
 
     let address = pop_arg!(args, AccountAddress);
 
-    let exists = context
+    let resource = context
         .get_resource(address, struct_tag.clone())
         .map_err(|err| {
             PartialVMError::new(StatusCode::VM_EXTENSION_ERROR).with_message(format!(
                 "Failed to read resource: {} at {}. With error: {}",
                 struct_tag, address, err
             ))
-        })?
-        .exists()
-        .map_err(|err| {
+        })?;
+
+    let exists = resource.exists().map_err(|err| {
+        PartialVMError::new(StatusCode::VM_EXTENSION_ERROR).with_message(format!(
+            "Failed to read resource: {} at {}. With error: {}",
+            struct_tag, address, err
+        ))
+    })?;
+
+    let loaded_size = resource_size(context, address, &struct_tag)?;
+    let cost =
+        gas_params.base_cost + gas_params.per_byte_cost * loaded_size.unwrap_or(NumBytes::new(0));
+
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(exists)]))
+}
+
+pub fn make_native_exists_at(gas_params: ExistsAtGasParameters) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| native_exists_at(&gas_params, context, ty_args, args))
+}
+
+/***************************************************************************************************
+ * native exists_batch<T>
+ *
+ *   gas cost: base_cost + per_item_cost * num_addresses + per_byte_cost * sum(size_of_resource)
+ *
+ **************************************************************************************************/
+fn native_exists_batch(
+    gas_params: &ExistsAtGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    assert!(ty_args.len() == 1);
+    assert!(args.len() == 1);
+
+    let type_tag = context.type_to_type_tag(&ty_args[0])?;
+    let struct_tag = if let TypeTag::Struct(struct_tag) = type_tag {
+        *struct_tag
+    } else {
+        return Ok(NativeResult::err(
+            gas_params.base_cost,
+            super::status::NFE_EXPECTED_STRUCT_TYPE_TAG,
+        ));
+    };
+
+    let addresses = pop_arg!(args, Vec<AccountAddress>);
+
+    let mut cost =
+        gas_params.base_cost + gas_params.per_item_cost * NumArgs::new(addresses.len() as u64);
+
+    let mut exists_vec = Vec::with_capacity(addresses.len());
+    for address in addresses {
+        // Charge every fetch the same per-byte rate `exists_at` does; only `base_cost` is paid
+        // once per batch instead of once per address, with `per_item_cost` charged per address.
+        let resource = context
+            .get_resource(address, struct_tag.clone())
+            .map_err(|err| {
+                PartialVMError::new(StatusCode::VM_EXTENSION_ERROR).with_message(format!(
+                    "Failed to read resource: {} at {}. With error: {}",
+                    struct_tag, address, err
+                ))
+            })?;
+
+        let exists = resource.exists().map_err(|err| {
             PartialVMError::new(StatusCode::VM_EXTENSION_ERROR).with_message(format!(
                 "Failed to read resource: {} at {}. With error: {}",
                 struct_tag, address, err
             ))
         })?;
+        let loaded_size = resource_size(context, address, &struct_tag)?;
+        cost += gas_params.per_byte_cost * loaded_size.unwrap_or(NumBytes::new(0));
+
+        exists_vec.push(exists);
+    }
 
-    Ok(NativeResult::ok(gas_params.base_cost, smallvec![
-        Value::bool(exists)
-    ]))
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::vector_bool(exists_vec)],
+    ))
 }
 
-pub fn make_native_exists_at(gas_params: ExistsAtGasParameters) -> NativeFunction {
-    Arc::new(move |context, ty_args, args| native_exists_at(&gas_params, context, ty_args, args))
+pub fn make_native_exists_batch(gas_params: ExistsAtGasParameters) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| native_exists_batch(&gas_params, context, ty_args, args))
+}
+
+/***************************************************************************************************
+ * native resource_group_size<Group>
+ * native resource_group_types<Group>
+ *
+ *   gas cost: base_cost + per_item_cost * num_members
+ *
+ **************************************************************************************************/
+#[derive(Clone, Debug)]
+pub struct ResourceGroupGasParameters {
+    pub base_cost: InternalGas,
+    pub per_item_cost: InternalGasPerArg,
+}
+
+fn native_resource_group_size(
+    gas_params: &ResourceGroupGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    assert!(ty_args.len() == 1);
+    assert!(args.len() == 1);
+
+    let type_tag = context.type_to_type_tag(&ty_args[0])?;
+    let group_tag = if let TypeTag::Struct(group_tag) = type_tag {
+        *group_tag
+    } else {
+        return Ok(NativeResult::err(
+            gas_params.base_cost,
+            super::status::NFE_EXPECTED_STRUCT_TYPE_TAG,
+        ));
+    };
+
+    let address = pop_arg!(args, AccountAddress);
+
+    let members = resource_group_members(context, address, &group_tag)?;
+    let cost = gas_params.base_cost + gas_params.per_item_cost * NumArgs::new(members.len() as u64);
+
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::u64(members.len() as u64)],
+    ))
+}
+
+pub fn make_native_resource_group_size(gas_params: ResourceGroupGasParameters) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        native_resource_group_size(&gas_params, context, ty_args, args)
+    })
+}
+
+fn std_string_type(context: &NativeContext) -> PartialVMResult<Type> {
+    context.load_type(&TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::ONE,
+        module: Identifier::new("string").unwrap(),
+        name: Identifier::new("String").unwrap(),
+        type_params: vec![],
+    })))
+}
+
+fn native_resource_group_types(
+    gas_params: &ResourceGroupGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    assert!(ty_args.len() == 1);
+    assert!(args.len() == 1);
+
+    let type_tag = context.type_to_type_tag(&ty_args[0])?;
+    let group_tag = if let TypeTag::Struct(group_tag) = type_tag {
+        *group_tag
+    } else {
+        return Ok(NativeResult::err(
+            gas_params.base_cost,
+            super::status::NFE_EXPECTED_STRUCT_TYPE_TAG,
+        ));
+    };
+
+    let address = pop_arg!(args, AccountAddress);
+
+    let members = resource_group_members(context, address, &group_tag)?;
+    let cost = gas_params.base_cost + gas_params.per_item_cost * NumArgs::new(members.len() as u64);
+
+    // `String` is `std::string::String { bytes: vector<u8> }`; pack one per member, then let
+    // `Vector::pack` build the returned `vector<String>` against its loaded element type, the
+    // same typed-container construction every other struct-returning native in this codebase
+    // uses, instead of a test-only vector constructor.
+    let string_type = std_string_type(context)?;
+    let type_strings = members.into_iter().map(|tag| {
+        Value::struct_(Struct::pack(vec![Value::vector_u8(
+            tag.to_string().into_bytes(),
+        )]))
+    });
+
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Vector::pack(&string_type, type_strings)?],
+    ))
+}
+
+pub fn make_native_resource_group_types(gas_params: ResourceGroupGasParameters) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        native_resource_group_types(&gas_params, context, ty_args, args)
+    })
+}
+
+/***************************************************************************************************
+ * native resource_version<T>
+ *
+ *   gas cost: base_cost
+ *
+ **************************************************************************************************/
+#[derive(Clone, Debug)]
+pub struct ResourceVersionGasParameters {
+    pub base_cost: InternalGas,
+}
+
+fn native_resource_version(
+    gas_params: &ResourceVersionGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    assert!(ty_args.len() == 1);
+    assert!(args.len() == 1);
+
+    let type_tag = context.type_to_type_tag(&ty_args[0])?;
+    let struct_tag = if let TypeTag::Struct(struct_tag) = type_tag {
+        *struct_tag
+    } else {
+        return Ok(NativeResult::err(
+            gas_params.base_cost,
+            super::status::NFE_EXPECTED_STRUCT_TYPE_TAG,
+        ));
+    };
+
+    let address = pop_arg!(args, AccountAddress);
+
+    // Reuses the state store's per-key write counter, so a Move caller can snapshot this, do
+    // some reads, and later assert it is unchanged before committing a dependent write.
+    let version = resource_version(context, address, &struct_tag)?;
+
+    Ok(NativeResult::ok(
+        gas_params.base_cost,
+        smallvec![Value::u64(version)],
+    ))
+}
+
+pub fn make_native_resource_version(gas_params: ResourceVersionGasParameters) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        native_resource_version(&gas_params, context, ty_args, args)
+    })
 }
 
 /***************************************************************************************************
@@ -77,20 +386,77 @@ pub fn make_native_exists_at(gas_params: ExistsAtGasParameters) -> NativeFunctio
 #[derive(Debug, Clone)]
 pub struct GasParameters {
     pub exists_at: ExistsAtGasParameters,
+    pub resource_group: ResourceGroupGasParameters,
+    pub resource_version: ResourceVersionGasParameters,
 }
 
 impl GasParameters {
-    pub fn new(exists_at_base: InternalGas) -> Self {
+    // Takes the three already-distinct gas-parameter structs rather than their fields flattened
+    // out positionally: every added native was growing this list with another same-typed
+    // `InternalGas`/`InternalGasPerArg`, which is exactly the shape that invites a transposed
+    // argument to compile silently.
+    pub fn new(
+        exists_at: ExistsAtGasParameters,
+        resource_group: ResourceGroupGasParameters,
+        resource_version: ResourceVersionGasParameters,
+    ) -> Self {
         Self {
-            exists_at: ExistsAtGasParameters {
-                base_cost: exists_at_base,
-            },
+            exists_at,
+            resource_group,
+            resource_version,
         }
     }
 }
 
 pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
-    let natives = [("exists_at", make_native_exists_at(gas_params.exists_at))];
+    let natives = [
+        (
+            "exists_at",
+            make_native_exists_at(gas_params.exists_at.clone()),
+        ),
+        (
+            "exists_batch",
+            make_native_exists_batch(gas_params.exists_at),
+        ),
+        (
+            "resource_group_size",
+            make_native_resource_group_size(gas_params.resource_group.clone()),
+        ),
+        (
+            "resource_group_types",
+            make_native_resource_group_types(gas_params.resource_group),
+        ),
+        (
+            "resource_version",
+            make_native_resource_version(gas_params.resource_version),
+        ),
+    ];
 
     crate::natives::helpers::make_module_natives(natives)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `native_exists_at`/`native_exists_batch` charge `per_byte_cost * loaded_size` on top of
+    // `base_cost`; assert the scaling directly on the cost formula rather than through a full
+    // VM run, since a larger resource must always cost strictly more to load than a small one.
+    #[test]
+    fn exists_at_cost_scales_with_resource_size() {
+        let gas_params = ExistsAtGasParameters {
+            base_cost: InternalGas::new(100),
+            per_item_cost: InternalGasPerArg::new(10),
+            per_byte_cost: InternalGasPerByte::new(1),
+        };
+
+        let small_cost = gas_params.base_cost + gas_params.per_byte_cost * NumBytes::new(32);
+        let large_cost = gas_params.base_cost + gas_params.per_byte_cost * NumBytes::new(32_000);
+
+        assert!(large_cost > small_cost);
+        assert_eq!(
+            large_cost - small_cost,
+            gas_params.per_byte_cost * NumBytes::new(32_000 - 32)
+        );
+    }
+}